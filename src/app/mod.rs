@@ -1,11 +1,16 @@
 pub mod view;
+mod stats;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use rand::prelude::SliceRandom;
-use std::{collections::VecDeque, fs::File, io::{BufReader, BufRead}, iter::FromIterator, sync::mpsc::{self, Receiver, Sender}, time::{Duration, SystemTime}};
+use std::{collections::{HashSet, VecDeque}, fs::File, io::{BufReader, BufRead}, iter::FromIterator, sync::mpsc::{self, Receiver, Sender}, time::{Duration, SystemTime}};
 
 pub enum UIEvent {
     Input(KeyEvent),
+    Paste(String),
     Tick,
+    /// Terminal size changed; carries no data, just wakes the main loop
+    /// to redraw against the new size.
+    Resize,
 }
 
 /// Controller instance provides an interface layer for the game data
@@ -16,6 +21,7 @@ pub enum UIEvent {
 /// exit_rx.
 pub struct Controller {
     pub model: Model,
+    stats_db: stats::StatsDb,
     exit_tx: Sender<()>,
 }
 
@@ -25,8 +31,12 @@ impl Controller {
     /// on close events sent by the controller.
     pub fn new() -> (Self, Receiver<()>) {
         let (exit_tx, exit_rx) = mpsc::channel();
+        let stats_db = stats::StatsDb::open()
+            .expect("Failed to open results database.");
+        let model = Model::new(&stats_db);
         let controller = Self {
-            model: Model::default(),
+            model,
+            stats_db,
             exit_tx,
         };
 
@@ -34,15 +44,27 @@ impl Controller {
     }
 
     pub fn update(&mut self) {
-        let elapsed = self.model.start.elapsed()
-                                      .expect("Failed to get system time.");
+        if self.model.round_state != RoundState::Active {
+            return;
+        }
 
-        
-        // Test if the timer has expired during an active round
-        if self.model.round_state == RoundState::Active && 
-            elapsed.as_millis() >= 30_000 {
-            
-            self.end_round();
+        // Word-count mode ends in submit_word once enough words are
+        // tried; only time mode needs to watch the clock here.
+        if let TestMode::Time(time_mode) = self.model.config.mode {
+            let elapsed = self.model.start.elapsed()
+                                          .expect("Failed to get system time.");
+            if elapsed.as_secs() >= time_mode.as_secs() {
+                self.end_round();
+            }
+        }
+    }
+
+    /// Handle a bracketed-paste event. Pasted text is never inserted
+    /// into `word_typing` - during an active round it instead flags the
+    /// round as invalidated so an inflated score isn't presented as real.
+    pub fn handle_paste_event(&mut self, _text: String) {
+        if self.model.round_state == RoundState::Active {
+            self.model.round_invalidated = true;
         }
     }
 
@@ -50,19 +72,45 @@ impl Controller {
         match event.code {
             KeyCode::Esc => self.exit_tx.send(()).expect("Failed to send exit signal."),
             KeyCode::Backspace => { self.model.word_typing.pop(); },
-            KeyCode::Enter => {
-                if self.model.round_state == RoundState::Completed {
-                    self.model.word_queue = 
-                        Model::new_word_list(&self.model.dict, 200);
+            KeyCode::Enter => match self.model.round_state {
+                RoundState::Menu => {
+                    self.model.word_queue = Model::new_word_list(
+                        &self.model.dict, &self.stats_db, self.model.config.queue_len());
+                    self.model.round_state = RoundState::Stopped;
+                }
+                RoundState::Completed | RoundState::History => {
+                    self.model.word_queue = Model::new_word_list(
+                        &self.model.dict, &self.stats_db, self.model.config.queue_len());
 
                     self.model.chars_correct = 0;
                     self.model.chars_wrong = 0;
 
                     self.model.words_entered.clear();
                     self.model.words_tried.clear();
+                    self.model.round_invalidated = false;
 
                     self.model.round_state = RoundState::Stopped;
                 }
+                _ => {}
+            },
+            KeyCode::Char('h') if self.model.round_state == RoundState::Completed => {
+                self.model.history = self.stats_db.history(10).ok();
+                self.model.round_state = RoundState::History;
+            }
+            KeyCode::Char('m') if self.model.round_state == RoundState::Stopped => {
+                self.model.round_state = RoundState::Menu;
+            }
+            KeyCode::Char(c) if self.model.round_state == RoundState::Menu => {
+                match c {
+                    '1' => self.model.config.mode = TestMode::Time(TimeMode::Secs15),
+                    '2' => self.model.config.mode = TestMode::Time(TimeMode::Secs30),
+                    '3' => self.model.config.mode = TestMode::Time(TimeMode::Secs60),
+                    '4' => self.model.config.mode = TestMode::Time(TimeMode::Secs120),
+                    '5' => self.model.config.mode = TestMode::Words(25),
+                    '6' => self.model.config.mode = TestMode::Words(50),
+                    '7' => self.model.config.mode = TestMode::Words(100),
+                    _ => {}
+                }
             }
             KeyCode::Char(c) => {
                 let mut ascii_char = c;
@@ -77,6 +125,7 @@ impl Controller {
                             // Start the game
                             self.model.round_state = RoundState::Active;
                             self.model.start = SystemTime::now();
+                            self.model.word_start = SystemTime::now();
                         }
                             
                         self.model.word_typing.push(ascii_char);
@@ -91,29 +140,64 @@ impl Controller {
     fn end_round(&mut self) {
         self.model.round_state = RoundState::Completed;
         self.model.word_queue.clear();
+
+        let (correct, wrong) = (self.model.chars_correct, self.model.chars_wrong);
+        let total = correct + wrong;
+        let accuracy = if total == 0 { 0.0 } else { correct as f64 / total as f64 };
+        let elapsed_secs = self.model.start.elapsed()
+            .expect("Failed to get system time.")
+            .as_secs();
+        let gross_wpm = (total as f64 / 5.0) / (elapsed_secs.max(1) as f64 / 60.0);
+        let adjusted_wpm = gross_wpm * accuracy;
+
+        let record = stats::RoundRecord {
+            timestamp: stats::now_unix(),
+            adjusted_wpm,
+            gross_wpm,
+            accuracy,
+            chars_correct: correct,
+            chars_wrong: wrong,
+            elapsed_secs,
+        };
+
+        if let Err(e) = self.stats_db.insert_round(&record) {
+            eprintln!("Failed to save round results: {:?}", e);
+        }
+
+        self.model.last_result = Some(record);
     }
 
     fn submit_word(&mut self){
-        let mut typed_word = self.model.word_typing.chars();
+        let tar_word = match self.model.word_queue.front() {
+            Some(word) => word.clone(),
+            // There is no active word, so just clear and do nothing
+            None => {
+                self.model.word_typing.clear();
+                return;
+            }
+        };
 
-        if let Some(tar_word) = self.model.word_queue.front() {
-            for ch in tar_word.chars() {
-                if let Some(typed_ch) = typed_word.next() {
-                    if ch == typed_ch {
-                        self.model.chars_correct += 1;
-                    }
-                    else{
-                        self.model.chars_wrong += 1;
-                    }
+        let mut correct_chars = 0;
+        let mut wrong_chars = 0;
+        let mut typed_word = self.model.word_typing.chars();
+        for ch in tar_word.chars() {
+            if let Some(typed_ch) = typed_word.next() {
+                if ch == typed_ch {
+                    correct_chars += 1;
+                }
+                else{
+                    wrong_chars += 1;
                 }
             }
         }
-        // There is no active word, so just clear and do nothing
-        else {
-            self.model.word_typing.clear();
-            return;
-        }
+        self.model.chars_correct += correct_chars;
+        self.model.chars_wrong += wrong_chars;
 
+        let is_correct = wrong_chars == 0 &&
+            self.model.word_typing.chars().count() == tar_word.chars().count();
+        let elapsed = self.model.word_start.elapsed().unwrap_or_default();
+        self.update_word_mastery(&tar_word, is_correct, elapsed);
+        self.model.word_start = SystemTime::now();
 
         // Save the user's attempted word
         self.model.words_entered.push(self.model.word_typing.clone());
@@ -123,14 +207,102 @@ impl Controller {
         if let Some(word) = self.model.word_queue.pop_front() {
             self.model.words_tried.push(word);
         }
+
+        if let TestMode::Words(target) = self.model.config.mode {
+            if self.model.words_tried.len() >= target {
+                self.end_round();
+            }
+        }
+    }
+
+    /// Derive a quality score for the word just typed and fold it into
+    /// its saved spaced-repetition state.
+    fn update_word_mastery(&mut self, word: &str, correct: bool, elapsed: Duration) {
+        let q = quality_score(correct, elapsed, word.chars().count());
+
+        let mut mastery = self.stats_db.word_mastery(word)
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| stats::WordMastery::new(word));
+
+        mastery.update(q);
+
+        if let Err(e) = self.stats_db.upsert_word_mastery(&mastery) {
+            eprintln!("Failed to save word mastery: {:?}", e);
+        }
+    }
+}
+
+/// Map correctness and typing speed for a word to an SM-2 quality score
+/// in `0..=5`: any mistake scores low, correct-but-slow scores middling,
+/// correct-and-fast scores the maximum.
+fn quality_score(correct: bool, elapsed: Duration, word_len: usize) -> u8 {
+    if !correct {
+        return 2;
     }
+
+    let fast_threshold = Duration::from_millis(150 * word_len.max(1) as u64);
+    if elapsed <= fast_threshold { 5 } else { 4 }
 }
 
 #[derive(PartialEq)]
 pub enum RoundState {
+    /// Pre-round mode selection, reached from the stopped screen with `m`.
+    Menu,
     Stopped,
     Active,
     Completed,
+    /// Viewing past results, reached from the completed screen.
+    History,
+}
+
+/// Fixed round durations selectable from the pre-round menu.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TimeMode {
+    Secs15,
+    Secs30,
+    Secs60,
+    Secs120,
+}
+
+impl TimeMode {
+    pub fn as_secs(&self) -> u64 {
+        match self {
+            TimeMode::Secs15  => 15,
+            TimeMode::Secs30  => 30,
+            TimeMode::Secs60  => 60,
+            TimeMode::Secs120 => 120,
+        }
+    }
+}
+
+/// How a round decides it's finished: after a fixed duration, or after
+/// a fixed number of words.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TestMode {
+    Time(TimeMode),
+    Words(usize),
+}
+
+/// User-selectable round settings, chosen from the pre-round menu.
+pub struct Config {
+    pub mode: TestMode,
+}
+
+impl Config {
+    fn default() -> Self {
+        Self { mode: TestMode::Time(TimeMode::Secs30) }
+    }
+
+    /// Size of the word queue to build for the current mode: exactly the
+    /// target word count in word mode, a generous buffer in time mode so
+    /// fast typers never run out before the clock does.
+    fn queue_len(&self) -> usize {
+        match self.mode {
+            TestMode::Words(n) => n,
+            TestMode::Time(_) => 400,
+        }
+    }
 }
 
 /// Holds all the data relevant to UI and gamestates
@@ -147,35 +319,69 @@ pub struct Model {
     pub chars_wrong:    usize,
     /// SystemTime at round start
     pub start:          SystemTime,
+    /// SystemTime the current word began being typed, used to gauge
+    /// per-word typing speed for spaced repetition
+    word_start:         SystemTime,
     /// Current state of round
     pub round_state:    RoundState,
+    /// WPM/accuracy for the most recently completed round
+    pub last_result:    Option<stats::RoundRecord>,
+    /// Recent rounds plus rolling average/personal best, fetched when the
+    /// history screen is opened
+    pub history:        Option<stats::HistorySummary>,
+    /// Set when a paste was detected during the active round, marking
+    /// its result as untrustworthy
+    pub round_invalidated: bool,
+    /// User-selected round duration/length
+    pub config:         Config,
     /// Dictionary file loaded to memory
     dict:               Vec<String>,
 }
 
 impl Model {
-    pub fn default() -> Self {
+    pub fn new(stats_db: &stats::StatsDb) -> Self {
         let dict = Self::load_dictionary();
-        Self { word_queue: Self::new_word_list(&dict, 200),
+        let config = Config::default();
+        Self { word_queue: Self::new_word_list(&dict, stats_db, config.queue_len()),
                word_typing: String::new(),
                words_tried: Vec::new(),
                words_entered: Vec::new(),
                chars_correct: 0,
                chars_wrong: 0,
                start: SystemTime::now(),
+               word_start: SystemTime::now(),
                round_state: RoundState::Stopped,
+               last_result: None,
+               history: None,
+               round_invalidated: false,
+               config,
                dict}
     }
-    
-    /// Clone a ring buffer of words using random words from a
-    /// &Vec<String>.
-    pub fn new_word_list(dict: &Vec<String>, num_words: usize) 
+
+    /// Build a ring buffer of words to drill: a fraction pulled from
+    /// words due for spaced-repetition review (weakest ease factor
+    /// first), the rest chosen uniformly at random, then shuffled
+    /// together so review words aren't all front-loaded.
+    pub fn new_word_list(dict: &Vec<String>, stats_db: &stats::StatsDb, num_words: usize)
         -> VecDeque<String> {
-    
+
         let mut rng = rand::thread_rng();
-        let random_words_iter = dict.choose_multiple(&mut rng, num_words)
-                                    .map(|word| word.clone());
-        VecDeque::from_iter(random_words_iter)
+
+        let review_quota = num_words / 3;
+        let due_words = stats_db.due_words(review_quota).unwrap_or_default();
+        let due_set: HashSet<&str> = due_words.iter().map(|w| w.as_str()).collect();
+
+        let random_quota = num_words.saturating_sub(due_words.len());
+        let random_pool: Vec<&String> = dict.iter()
+            .filter(|word| !due_set.contains(word.as_str()))
+            .collect();
+        let mut words: Vec<String> = random_pool.choose_multiple(&mut rng, random_quota)
+            .map(|word| (*word).clone())
+            .collect();
+        words.extend(due_words);
+        words.shuffle(&mut rng);
+
+        VecDeque::from_iter(words)
     }
 
     /// Load the dictionary file into memory.
@@ -0,0 +1,229 @@
+//! Persistence for completed rounds, backed by a local SQLite database.
+use rusqlite::{params, Connection, OptionalExtension};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A single completed round, as persisted to the results database.
+pub struct RoundRecord {
+    pub timestamp:     u64,
+    pub adjusted_wpm:  f64,
+    pub gross_wpm:     f64,
+    pub accuracy:      f64,
+    pub chars_correct: usize,
+    pub chars_wrong:   usize,
+    pub elapsed_secs:  u64,
+}
+
+/// Rolling view over recent rounds, computed for the history screen.
+pub struct HistorySummary {
+    pub recent:        Vec<RoundRecord>,
+    pub rolling_avg:   f64,
+    pub personal_best: f64,
+}
+
+/// SM-2 style spaced-repetition state tracked per dictionary word.
+pub struct WordMastery {
+    pub word:     String,
+    pub ef:       f64,
+    pub reps:     u32,
+    pub interval: u32,
+    pub due:      u64,
+}
+
+impl WordMastery {
+    /// A word with no prior history: default ease, due immediately.
+    pub fn new(word: &str) -> Self {
+        Self { word: word.to_string(), ef: 2.5, reps: 0, interval: 0, due: now_unix() }
+    }
+
+    /// Apply an SM-2 style update given a quality score in `0..=5`
+    /// (derived from correctness and typing speed for this word).
+    pub fn update(&mut self, q: u8) {
+        let q = q as f64;
+        self.ef = (self.ef + (0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02))).max(1.3);
+
+        if q < 3.0 {
+            self.reps = 0;
+            self.interval = 1;
+        } else {
+            self.reps += 1;
+            self.interval = ((self.interval.max(1) as f64) * self.ef).round() as u32;
+        }
+
+        self.due = now_unix() + (self.interval as u64 * 86_400);
+    }
+}
+
+/// Thin wrapper around the local SQLite results database.
+pub struct StatsDb {
+    conn: Connection,
+}
+
+impl StatsDb {
+    /// Open (creating if necessary) the results database and bring the
+    /// schema up to date.
+    pub fn open() -> rusqlite::Result<Self> {
+        let conn = Connection::open("wpm_results.db")?;
+        let db = Self { conn };
+        db.migrate()?;
+        Ok(db)
+    }
+
+    /// Apply any migrations that haven't been recorded in the
+    /// `migrations` table yet, so re-opening the database is idempotent.
+    fn migrate(&self) -> rusqlite::Result<()> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS migrations (
+                id         INTEGER PRIMARY KEY,
+                applied_at INTEGER NOT NULL
+            );"
+        )?;
+
+        self.apply_migration(1, "CREATE TABLE rounds (
+            id             INTEGER PRIMARY KEY,
+            timestamp      INTEGER NOT NULL,
+            adjusted_wpm   REAL NOT NULL,
+            gross_wpm      REAL NOT NULL,
+            accuracy       REAL NOT NULL,
+            chars_correct  INTEGER NOT NULL,
+            chars_wrong    INTEGER NOT NULL,
+            elapsed_secs   INTEGER NOT NULL
+        );")?;
+
+        self.apply_migration(2, "CREATE TABLE word_mastery (
+            word      TEXT PRIMARY KEY,
+            ef        REAL NOT NULL,
+            reps      INTEGER NOT NULL,
+            interval  INTEGER NOT NULL,
+            due       INTEGER NOT NULL
+        );")?;
+
+        Ok(())
+    }
+
+    /// Run `sql` once, recording `id` in the `migrations` table so it is
+    /// never applied twice.
+    fn apply_migration(&self, id: i64, sql: &str) -> rusqlite::Result<()> {
+        let applied: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM migrations WHERE id = ?1",
+            params![id],
+            |row| row.get(0))?;
+
+        if applied == 0 {
+            self.conn.execute_batch(sql)?;
+            self.conn.execute(
+                "INSERT INTO migrations (id, applied_at) VALUES (?1, ?2)",
+                params![id, now_unix() as i64])?;
+        }
+
+        Ok(())
+    }
+
+    /// Persist a single completed round.
+    pub fn insert_round(&self, record: &RoundRecord) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO rounds
+                (timestamp, adjusted_wpm, gross_wpm, accuracy, chars_correct, chars_wrong, elapsed_secs)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                record.timestamp as i64,
+                record.adjusted_wpm,
+                record.gross_wpm,
+                record.accuracy,
+                record.chars_correct as i64,
+                record.chars_wrong as i64,
+                record.elapsed_secs as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Fetch the last `n` rounds (most recent first) plus their rolling
+    /// average and the personal best over the whole history.
+    pub fn history(&self, n: usize) -> rusqlite::Result<HistorySummary> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, adjusted_wpm, gross_wpm, accuracy, chars_correct, chars_wrong, elapsed_secs
+             FROM rounds ORDER BY id DESC LIMIT ?1")?;
+
+        let recent: Vec<RoundRecord> = stmt.query_map(params![n as i64], |row| {
+            Ok(RoundRecord {
+                timestamp:     row.get::<_, i64>(0)? as u64,
+                adjusted_wpm:  row.get(1)?,
+                gross_wpm:     row.get(2)?,
+                accuracy:      row.get(3)?,
+                chars_correct: row.get::<_, i64>(4)? as usize,
+                chars_wrong:   row.get::<_, i64>(5)? as usize,
+                elapsed_secs:  row.get::<_, i64>(6)? as u64,
+            })
+        })?.collect::<rusqlite::Result<_>>()?;
+
+        let rolling_avg = if recent.is_empty() {
+            0.0
+        } else {
+            recent.iter().map(|r| r.adjusted_wpm).sum::<f64>() / recent.len() as f64
+        };
+
+        let personal_best: f64 = self.conn.query_row(
+            "SELECT COALESCE(MAX(adjusted_wpm), 0.0) FROM rounds", [], |row| row.get(0))?;
+
+        Ok(HistorySummary { recent, rolling_avg, personal_best })
+    }
+
+    /// Fetch the saved mastery state for `word`, if any has been recorded.
+    pub fn word_mastery(&self, word: &str) -> rusqlite::Result<Option<WordMastery>> {
+        self.conn.query_row(
+            "SELECT word, ef, reps, interval, due FROM word_mastery WHERE word = ?1",
+            params![word],
+            |row| Ok(WordMastery {
+                word:     row.get(0)?,
+                ef:       row.get(1)?,
+                reps:     row.get::<_, i64>(2)? as u32,
+                interval: row.get::<_, i64>(3)? as u32,
+                due:      row.get::<_, i64>(4)? as u64,
+            }),
+        ).optional()
+    }
+
+    /// Insert or update the mastery state for a word.
+    pub fn upsert_word_mastery(&self, mastery: &WordMastery) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO word_mastery (word, ef, reps, interval, due)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(word) DO UPDATE SET
+                ef = excluded.ef,
+                reps = excluded.reps,
+                interval = excluded.interval,
+                due = excluded.due",
+            params![
+                mastery.word,
+                mastery.ef,
+                mastery.reps as i64,
+                mastery.interval as i64,
+                mastery.due as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Words with saved mastery state whose review is due, lowest ease
+    /// factor first so the weakest words resurface earliest. Words never
+    /// typed before have no saved state and are not returned.
+    pub fn due_words(&self, limit: usize) -> rusqlite::Result<Vec<String>> {
+        let now = now_unix() as i64;
+        let mut stmt = self.conn.prepare(
+            "SELECT word FROM word_mastery
+             WHERE due <= ?1
+             ORDER BY ef ASC
+             LIMIT ?2")?;
+
+        let words = stmt.query_map(params![now, limit as i64], |row| row.get(0))?
+            .collect();
+        words
+    }
+}
+
+/// Seconds since the unix epoch, used for timestamping rows.
+pub(crate) fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+        .expect("system clock is before the unix epoch")
+        .as_secs()
+}
@@ -1,6 +1,4 @@
-use std::{collections::VecDeque, iter::FromIterator};
-
-use super::{Model, RoundState};
+use super::{Model, RoundState, TestMode, TimeMode};
 use tui::{
     Frame, 
     backend::Backend, 
@@ -11,13 +9,21 @@ use tui::{
 };
 
 pub fn render<B: Backend>(f: &mut Frame<B>, model: &Model) {
+    let term_size = f.size();
+
+    // Grow the center column on wide terminals and collapse it
+    // gracefully on narrow ones, rather than wasting/overflowing space
+    // with a fixed percentage split.
+    let center_width = term_size.width.saturating_sub(10).clamp(40, 120);
+    let side_padding = term_size.width.saturating_sub(center_width) / 2;
+
     let padding_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(25),
-                      Constraint::Percentage(50),
-                      Constraint::Percentage(25)].as_ref())
-        .split(f.size());
-    
+        .constraints([Constraint::Length(side_padding),
+                      Constraint::Length(center_width),
+                      Constraint::Min(0)].as_ref())
+        .split(term_size);
+
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(50),
@@ -25,7 +31,7 @@ pub fn render<B: Backend>(f: &mut Frame<B>, model: &Model) {
                       Constraint::Length(3),
                       Constraint::Min(0)].as_ref())
         .split(padding_chunks[1]);
-    
+
 
     let lower_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -33,6 +39,12 @@ pub fn render<B: Backend>(f: &mut Frame<B>, model: &Model) {
                       Constraint::Percentage(66)])
         .split(main_chunks[2]);
 
+    match model.round_state {
+        RoundState::History => return draw_history(f, padding_chunks[1], model),
+        RoundState::Menu => return draw_menu(f, padding_chunks[1], model),
+        _ => {}
+    }
+
     draw_words_list(f, main_chunks[1], model);
     draw_current_word(f, lower_chunks[0], model);
     draw_info(f, lower_chunks[1], model);
@@ -44,22 +56,31 @@ fn draw_info<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model) {
     let elapsed = model.start.elapsed().unwrap().as_secs();
     match model.round_state {
         RoundState::Active => {
+            let progress = match model.config.mode {
+                TestMode::Time(time_mode) =>
+                    format!("{}s", time_mode.as_secs().saturating_sub(elapsed)),
+                TestMode::Words(target) =>
+                    format!("{}/{} words", model.words_tried.len(), target),
+            };
+            let live_wpm = (model.chars_correct as f64 / 5.0) / (elapsed.max(1) as f64 / 60.0);
             timer_span = Span::from(format!(
-                                        "{}s | ~{} wpm", 
-                                        30-elapsed,
-                                        model.chars_correct/5*2)); 
+                                        "{} | ~{} wpm",
+                                        progress,
+                                        live_wpm as u64));
         },
         RoundState::Stopped => {
             timer_span = Span::from("---");
         },
         RoundState::Completed => {
-            let (correct, wrong) = (model.chars_correct, model.chars_wrong);
-            let total = correct+wrong;
-            let accuracy: f64 = (correct as f64) / (total as f64);
-            let gross_words: f64 = (total as f64) / 5.0;
-            let gross_wpm: f64 = gross_words * 2.0;
-            let adjusted_wpm: f64 = gross_wpm * accuracy;
-            timer_span = Span::from(format!("{} wpm", adjusted_wpm as u64));
+            let wpm = model.last_result.as_ref()
+                .map(|r| r.adjusted_wpm as u64)
+                .unwrap_or(0);
+            let flag = if model.round_invalidated { " (invalidated: paste detected)" } else { "" };
+            timer_span = Span::from(format!(
+                "{} wpm{} | <Enter> retry, <h> history", wpm, flag));
+        },
+        RoundState::History | RoundState::Menu => {
+            timer_span = Span::from("---");
         },
     }
 
@@ -71,61 +92,18 @@ fn draw_info<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model) {
 fn draw_words_list<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model){
     let mut next_words = model.word_queue.iter().take(10);
     let mut words: Vec<Span> = Vec::new();
+    let mut first = true;
+
     while let Some(word) = next_words.next() {
-        if words.is_empty() {
-            let mut green_chars = Vec::<char>::new();
-            let mut default_chars = Vec::<char>::new();
-
-            let mut target_word = VecDeque::from_iter(word.chars());
-            let mut typed_word=VecDeque::from_iter(model.word_typing.chars());
-
-            // Loop through all the chars of the target word
-            while let Some(tar_char) = target_word.pop_front(){
-                // Compare typed char with target char
-                if let Some(typed_char) = typed_word.pop_front() {
-                    if typed_char == tar_char {
-                        green_chars.push(typed_char);
-                    }
-                    else{
-                        // Push the entire word as a red style
-                        let style = Style::default().fg(Color::Red);
-                        words.push(Span::styled(word, style));
-
-                        // Clear the other styled entries
-                        green_chars.clear();
-                        default_chars.clear();
-                        
-                        // Break and push to be drawn
-                        break;
-                    }
-                }
-                // End of currently typed word, leave the rest as the default
-                // style
-                else{
-                    // Push the popped char back to the word
-                    target_word.push_front(tar_char);
-                    // Build a Vec<char> from the VecDeque
-                    default_chars = Vec::from_iter(
-                                        target_word.iter()
-                                                   .map(|c| c.clone()));
-                    // Break and push to be drawn
-                    break;
-                }
-            }
-
-            let green_string: String = green_chars.into_iter().collect(); 
-            words.push(Span::styled(green_string,
-                                    Style::default().fg(Color::Green)));
-            let default_string: String = default_chars.into_iter().collect(); 
-            words.push(Span::from(default_string));
+        if first {
+            words.extend(styled_current_word(word, &model.word_typing));
+            first = false;
         }
         else{
             words.push(Span::from(String::from(word)));
-        } 
+        }
 
         words.push(Span::from(" "));
-
-        
     }
 
     let text_box = Paragraph::new(Spans::from(words))
@@ -135,6 +113,38 @@ fn draw_words_list<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model){
     f.render_widget(text_box, area);
 }
 
+/// Style the word currently being typed character by character: correct
+/// characters green, mismatched characters red (drawn over the target
+/// glyph so word width stays stable), untyped characters dim, and the
+/// caret position reversed. Characters typed past the end of the word
+/// are appended as red surplus.
+fn styled_current_word<'a>(target: &'a str, typed: &str) -> Vec<Span<'a>> {
+    let target_chars: Vec<char> = target.chars().collect();
+    let typed_chars: Vec<char> = typed.chars().collect();
+    let mut spans = Vec::with_capacity(target_chars.len() + 1);
+
+    for (i, tar_char) in target_chars.iter().enumerate() {
+        let style = match typed_chars.get(i) {
+            Some(typed_char) if typed_char == tar_char => Style::default().fg(Color::Green),
+            Some(_) => Style::default().fg(Color::Red),
+            None if i == typed_chars.len() =>
+                Style::default().add_modifier(Modifier::REVERSED),
+            None => Style::default().add_modifier(Modifier::DIM),
+        };
+        spans.push(Span::styled(tar_char.to_string(), style));
+    }
+
+    if typed_chars.len() == target_chars.len() {
+        spans.push(Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)));
+    }
+    else if typed_chars.len() > target_chars.len() {
+        let surplus: String = typed_chars[target_chars.len()..].iter().collect();
+        spans.push(Span::styled(surplus, Style::default().fg(Color::Red)));
+    }
+
+    spans
+}
+
 fn draw_current_word<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model){
     let padding_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -150,3 +160,63 @@ fn draw_current_word<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model){
 
     f.render_widget(text_box, padding_chunks[0]);
 }
+
+/// Render the last few runs plus a rolling average and personal best,
+/// reached from the completed screen by pressing `h`.
+fn draw_history<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model) {
+    let items: Vec<ListItem> = match &model.history {
+        Some(summary) => summary.recent.iter()
+            .map(|r| ListItem::new(format!(
+                "{:>3} wpm  ({:.0}% acc)",
+                r.adjusted_wpm as u64,
+                r.accuracy * 100.0)))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let (rolling_avg, personal_best) = model.history.as_ref()
+        .map(|s| (s.rolling_avg, s.personal_best))
+        .unwrap_or((0.0, 0.0));
+
+    let title = format!("History  |  avg {:.0} wpm  |  best {:.0} wpm",
+                         rolling_avg, personal_best);
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    f.render_widget(list, area);
+}
+
+/// Pre-round menu for picking a time or word-count mode, reached from
+/// the stopped screen by pressing `m`.
+fn draw_menu<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model) {
+    let options: Vec<(&str, TestMode)> = vec![
+        ("1", TestMode::Time(TimeMode::Secs15)),
+        ("2", TestMode::Time(TimeMode::Secs30)),
+        ("3", TestMode::Time(TimeMode::Secs60)),
+        ("4", TestMode::Time(TimeMode::Secs120)),
+        ("5", TestMode::Words(25)),
+        ("6", TestMode::Words(50)),
+        ("7", TestMode::Words(100)),
+    ];
+
+    let items: Vec<ListItem> = options.iter().map(|(key, mode)| {
+        let label = match mode {
+            TestMode::Time(t) => format!("{}s", t.as_secs()),
+            TestMode::Words(n) => format!("{} words", n),
+        };
+        let selected = *mode == model.config.mode;
+        let style = if selected {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        ListItem::new(format!("[{}] {}", key, label)).style(style)
+    }).collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL)
+            .title("Select mode, then <Enter>"));
+
+    f.render_widget(list, area);
+}
@@ -15,7 +15,8 @@ use std::{
 
 use crossterm::{
     event::{ self, DisableMouseCapture, KeyModifiers,
-        EnableMouseCapture, Event, KeyCode },
+        EnableMouseCapture, DisableBracketedPaste, EnableBracketedPaste,
+        Event, KeyCode },
     terminal::{ EnterAlternateScreen, LeaveAlternateScreen,
         enable_raw_mode, disable_raw_mode},
     execute,
@@ -35,7 +36,7 @@ fn main() {
     
     enable_raw_mode().unwrap();
     let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture).unwrap();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste).unwrap();
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).unwrap();
 
@@ -49,14 +50,19 @@ fn main() {
         terminal.draw(|f| app::view::render(f, &controller.model)).unwrap();
        
         // Blocking read on events, this causes a redraw on new UIEvents ONLY
-        if let UIEvent::Input(key_ev) = event_rx.recv().unwrap() {
-            // Handle <Ctrl+C>
-            if let KeyModifiers::CONTROL = key_ev.modifiers {
-                if key_ev.code == KeyCode::Char('c') { break; }
-            }
-            controller.handle_key_event(key_ev);
-        } else {
-            controller.update();
+        match event_rx.recv().unwrap() {
+            UIEvent::Input(key_ev) => {
+                // Handle <Ctrl+C>
+                if let KeyModifiers::CONTROL = key_ev.modifiers {
+                    if key_ev.code == KeyCode::Char('c') { break; }
+                }
+                controller.handle_key_event(key_ev);
+            },
+            UIEvent::Paste(text) => controller.handle_paste_event(text),
+            UIEvent::Tick => controller.update(),
+            // Just wakes the loop to redraw against the new terminal
+            // size; no game state to mutate.
+            UIEvent::Resize => {},
         }
        
         // Non-blocking read on exit signals
@@ -86,14 +92,15 @@ fn spawn_event_loop(event_tx: Sender<UIEvent>, tick_rate: u64) -> Result<()> {
             let elapsed = last_tick.elapsed().unwrap();
             // Poll for new events
             if event::poll(tick_rate).unwrap() {
-                // Check for key events
-                if let Event::Key(key) = event::read().unwrap() {
-                    // Send the key event through the channel, closing
-                    // the thread on error
-                    if let Err(_) = event_tx.send(UIEvent::Input(key)) {
-                        break;
-                    }
-                }
+                // Forward the event through the channel, closing the
+                // thread on error
+                let sent = match event::read().unwrap() {
+                    Event::Key(key) => event_tx.send(UIEvent::Input(key)),
+                    Event::Paste(text) => event_tx.send(UIEvent::Paste(text)),
+                    Event::Resize(_, _) => event_tx.send(UIEvent::Resize),
+                    _ => Ok(()),
+                };
+                if let Err(_) = sent { break; }
             }
 
             if elapsed >= tick_rate {
@@ -111,7 +118,8 @@ fn spawn_event_loop(event_tx: Sender<UIEvent>, tick_rate: u64) -> Result<()> {
 fn kill_terminal(){
     execute!(stdout(),
         LeaveAlternateScreen,
-        DisableMouseCapture).unwrap();
+        DisableMouseCapture,
+        DisableBracketedPaste).unwrap();
     disable_raw_mode().unwrap();
 }
 